@@ -16,11 +16,681 @@
  * File_ex provides interfaces for operating on file.
  */
 
+use std::io;
+
 #[cxx::bridge(namespace = "OHOS")]
 /// Module file_ex::ffi. Includes interfaces which will call c++ counterparts via FFI.
+///
+/// Contract relied on by [`FileError::last`]: every function below returns
+/// `false` only after a failing POSIX call (`open`/`read`/`write`/`stat`),
+/// leaving `errno` set to that call's failure, exactly like the OHOS
+/// `file_ex.cpp` implementations they bind to. A function that fails a
+/// path-validation check before reaching a syscall is expected to set
+/// `errno` itself (e.g. to `EINVAL`) rather than return `false` silently, so
+/// Rust callers reading `errno` right after the call see the real cause.
 pub mod ffi {
-    #[allow(dead_code)]
     unsafe extern "C++" {
         include!("commonlibrary/c_utils/base/include/file_ex.h");
+
+        /// Loads the whole content of `file_path` into `content`, replacing it.
+        fn LoadStringFromFile(file_path: &CxxString, content: Pin<&mut CxxString>) -> bool;
+        /// Writes `content` to `file_path`, truncating any existing content when `truncated` is set.
+        fn SaveStringToFile(file_path: &CxxString, content: &CxxString, truncated: bool) -> bool;
+        /// Loads the whole content readable from `fd` into `content`.
+        fn LoadStringFromFd(fd: i32, content: Pin<&mut CxxString>) -> bool;
+        /// Writes `content` to the already-open file descriptor `fd`.
+        fn SaveStringToFd(fd: i32, content: &CxxString) -> bool;
+        /// Loads the whole content of `file_path` into the byte buffer `content`.
+        fn LoadBufferFromFile(file_path: &CxxString, content: &mut Vec<u8>) -> bool;
+        /// Writes the byte buffer `content` to `file_path`, truncating existing content when `truncated` is set.
+        fn SaveBufferToFile(file_path: &CxxString, content: &[u8], truncated: bool) -> bool;
+        /// Returns whether `str` occurs in `file_path`.
+        fn StringExistsInFile(file_path: &CxxString, str: &CxxString, case_sensitive: bool) -> bool;
+        /// Counts how many times `str` occurs in `file_path`.
+        fn CountStrInFile(file_path: &CxxString, str: &CxxString, case_sensitive: bool) -> i32;
+        /// Returns whether `file_name` exists on disk.
+        fn FileExists(file_name: &CxxString) -> bool;
+    }
+
+    extern "Rust" {
+        /// Returns whether `str` occurs in `file_path`, ignoring case.
+        fn string_exists_in_file_ignore_case(file_path: &str, str: &str) -> bool;
+        /// Returns whether `file_name` matches the shell-style glob `pattern`
+        /// (`*` for any run of characters, `?` for exactly one).
+        fn file_matches_glob(file_name: &str, pattern: &str) -> bool;
+        /// Returns whether the SHA-256 digest of `file_path`'s content equals
+        /// `expected_hex`, a lowercase hex-encoded 32-byte digest.
+        fn file_sha256_matches(file_path: &str, expected_hex: &str) -> bool;
+    }
+}
+
+/// The file_ex operation that failed, used by [`FileError`] to report which
+/// step of a call a failure happened at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileOp {
+    Open,
+    Read,
+    Write,
+    Permission,
+    NotFound,
+}
+
+/// Error returned by the safe file_ex wrappers in place of a bare `bool`,
+/// carrying which operation failed and the OS error code behind it, when one
+/// is available.
+#[derive(Debug)]
+pub struct FileError {
+    pub op: FileOp,
+    pub os_error: Option<i32>,
+}
+
+impl FileError {
+    /// Builds a `FileError` from the last OS error, refining `fallback_op`
+    /// into [`FileOp::NotFound`] or [`FileOp::Permission`] when the OS error
+    /// says so, and keeping `fallback_op` (the kind of call that failed,
+    /// e.g. [`FileOp::Open`] vs. [`FileOp::Read`]/[`FileOp::Write`])
+    /// otherwise. Relies on the errno-preserving contract documented on
+    /// [`ffi`]: this must be called immediately after the failing `ffi` call,
+    /// with nothing else able to touch `errno` in between.
+    fn last(fallback_op: FileOp) -> Self {
+        let os_error = io::Error::last_os_error();
+        let op = match os_error.kind() {
+            io::ErrorKind::NotFound => FileOp::NotFound,
+            io::ErrorKind::PermissionDenied => FileOp::Permission,
+            _ => fallback_op,
+        };
+        Self {
+            op,
+            os_error: os_error.raw_os_error(),
+        }
+    }
+}
+
+impl std::fmt::Display for FileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.os_error {
+            Some(code) => write!(f, "{:?} failed (os error {code})", self.op),
+            None => write!(f, "{:?} failed", self.op),
+        }
+    }
+}
+
+impl std::error::Error for FileError {}
+
+/// Result type returned by the safe file_ex wrappers.
+pub type FileResult<T> = std::result::Result<T, FileError>;
+
+/// Loads the whole content of the file at `path` into a `String`.
+pub fn load_string_from_file(path: &str) -> FileResult<String> {
+    cxx::let_cxx_string!(file_path = path);
+    cxx::let_cxx_string!(content = "");
+    if ffi::LoadStringFromFile(&file_path, content.as_mut()) {
+        Ok(String::from_utf8_lossy(content.as_bytes()).into_owned())
+    } else {
+        Err(FileError::last(FileOp::Open))
+    }
+}
+
+/// Writes `content` to the file at `path`, truncating any existing content when `truncated` is set.
+pub fn save_string_to_file(path: &str, content: &str, truncated: bool) -> FileResult<()> {
+    cxx::let_cxx_string!(file_path = path);
+    cxx::let_cxx_string!(cxx_content = content);
+    if ffi::SaveStringToFile(&file_path, &cxx_content, truncated) {
+        Ok(())
+    } else {
+        Err(FileError::last(FileOp::Write))
+    }
+}
+
+/// Loads the whole content readable from the open file descriptor `fd`.
+pub fn load_string_from_fd(fd: i32) -> FileResult<String> {
+    cxx::let_cxx_string!(content = "");
+    if ffi::LoadStringFromFd(fd, content.as_mut()) {
+        Ok(String::from_utf8_lossy(content.as_bytes()).into_owned())
+    } else {
+        Err(FileError::last(FileOp::Read))
+    }
+}
+
+/// Writes `content` to the already-open file descriptor `fd`.
+pub fn save_string_to_fd(fd: i32, content: &str) -> FileResult<()> {
+    cxx::let_cxx_string!(cxx_content = content);
+    if ffi::SaveStringToFd(fd, &cxx_content) {
+        Ok(())
+    } else {
+        Err(FileError::last(FileOp::Write))
+    }
+}
+
+/// Loads the whole content of the file at `path` into a byte buffer.
+pub fn load_buffer_from_file(path: &str) -> FileResult<Vec<u8>> {
+    cxx::let_cxx_string!(file_path = path);
+    let mut content = Vec::new();
+    if ffi::LoadBufferFromFile(&file_path, &mut content) {
+        Ok(content)
+    } else {
+        Err(FileError::last(FileOp::Open))
+    }
+}
+
+/// Writes the byte buffer `content` to the file at `path`, truncating any existing content when `truncated` is set.
+pub fn save_buffer_to_file(path: &str, content: &[u8], truncated: bool) -> FileResult<()> {
+    cxx::let_cxx_string!(file_path = path);
+    if ffi::SaveBufferToFile(&file_path, content, truncated) {
+        Ok(())
+    } else {
+        Err(FileError::last(FileOp::Write))
+    }
+}
+
+/// Returns whether `str` occurs in the file at `path`.
+pub fn string_exists_in_file(path: &str, str: &str, case_sensitive: bool) -> bool {
+    cxx::let_cxx_string!(file_path = path);
+    cxx::let_cxx_string!(needle = str);
+    ffi::StringExistsInFile(&file_path, &needle, case_sensitive)
+}
+
+/// Counts how many times `str` occurs in the file at `path`.
+pub fn count_str_in_file(path: &str, str: &str, case_sensitive: bool) -> i32 {
+    cxx::let_cxx_string!(file_path = path);
+    cxx::let_cxx_string!(needle = str);
+    ffi::CountStrInFile(&file_path, &needle, case_sensitive)
+}
+
+/// Returns whether `file_name` exists on disk.
+pub fn file_exists(file_name: &str) -> bool {
+    cxx::let_cxx_string!(name = file_name);
+    ffi::FileExists(&name)
+}
+
+/// One segment of a vectored read: the absolute byte offset into the file
+/// where the segment starts, and the buffer to fill.
+pub struct ReadSegment<'a> {
+    pub offset: u64,
+    pub buf: &'a mut [u8],
+}
+
+/// One segment of a vectored write: the absolute byte offset into the file
+/// where the segment starts, and the bytes to write.
+pub struct WriteSegment<'a> {
+    pub offset: u64,
+    pub buf: &'a [u8],
+}
+
+/// Performs one logical vectored read over the file at `path`, filling each
+/// segment's buffer in turn starting at its own offset. Returns the total
+/// number of bytes transferred. A short read is reported by a total smaller
+/// than the sum of the segment lengths; the segment where the short read
+/// occurred is filled up to exactly that many bytes and no later segment is
+/// touched. Empty segments are tolerated and contribute nothing.
+pub fn read_vectored_at(path: &str, segments: &mut [ReadSegment]) -> io::Result<usize> {
+    use std::os::unix::fs::FileExt;
+
+    let file = std::fs::File::open(path)?;
+    let mut total = 0usize;
+    for segment in segments.iter_mut() {
+        if segment.buf.is_empty() {
+            continue;
+        }
+        let n = file.read_at(segment.buf, segment.offset)?;
+        total += n;
+        if n < segment.buf.len() {
+            break;
+        }
+    }
+    Ok(total)
+}
+
+/// Performs one logical vectored write over the file at `path`, draining each
+/// segment's bytes in turn starting at its own offset. Returns the total
+/// number of bytes transferred, with the same short-write semantics as
+/// [`read_vectored_at`].
+pub fn write_vectored_at(path: &str, segments: &[WriteSegment]) -> io::Result<usize> {
+    use std::os::unix::fs::FileExt;
+
+    let file = std::fs::OpenOptions::new().write(true).open(path)?;
+    let mut total = 0usize;
+    for segment in segments.iter() {
+        if segment.buf.is_empty() {
+            continue;
+        }
+        let n = file.write_at(segment.buf, segment.offset)?;
+        total += n;
+        if n < segment.buf.len() {
+            break;
+        }
+    }
+    Ok(total)
+}
+
+/// Reads the file at `path` in fixed-size windows of `chunk_len` bytes,
+/// invoking `callback` with each chunk in order. The last chunk may be shorter
+/// than `chunk_len`. Stops once the file is exhausted or `callback` returns
+/// `false`, keeping memory use bounded to a single chunk regardless of file
+/// size. Reads go through the open file descriptor's `read(2)`, the same
+/// fd-based path `LoadStringFromFd` reads from, rather than buffering the
+/// whole file at once.
+pub fn load_file_chunked<F>(path: &str, chunk_len: usize, mut callback: F) -> io::Result<()>
+where
+    F: FnMut(&[u8]) -> bool,
+{
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![0u8; chunk_len.max(1)];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        if !callback(&buf[..n]) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Counts the number of lines in the file at `path` without buffering the
+/// whole content in memory, reading it in chunks via [`load_file_chunked`].
+pub fn count_lines(path: &str) -> io::Result<usize> {
+    let mut count = 0usize;
+    let mut saw_any = false;
+    let mut ends_with_newline = true;
+    load_file_chunked(path, 64 * 1024, |chunk| {
+        saw_any = true;
+        count += chunk.iter().filter(|&&b| b == b'\n').count();
+        ends_with_newline = chunk.last() == Some(&b'\n');
+        true
+    })?;
+    if saw_any && !ends_with_newline {
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Scans the file at `path` line by line, invoking `callback` with each line
+/// in order, without buffering the whole content in memory, reading it in
+/// chunks via [`load_file_chunked`]. Stops early if `callback` returns
+/// `false`.
+pub fn for_each_line<F>(path: &str, mut callback: F) -> io::Result<()>
+where
+    F: FnMut(&str) -> bool,
+{
+    let mut pending = Vec::new();
+    let mut stopped = false;
+    load_file_chunked(path, 64 * 1024, |chunk| {
+        pending.extend_from_slice(chunk);
+        while let Some(pos) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=pos).collect();
+            if !callback(&String::from_utf8_lossy(&line[..line.len() - 1])) {
+                stopped = true;
+                return false;
+            }
+        }
+        true
+    })?;
+    if !stopped && !pending.is_empty() {
+        callback(&String::from_utf8_lossy(&pending));
+    }
+    Ok(())
+}
+
+/// Returns whether `str` occurs in the file at `file_path`, ignoring case.
+/// Exposed to C++ through [`ffi`]; implemented in Rust rather than delegated
+/// to `CountStrInFile` so the case-folding rule lives in one place.
+fn string_exists_in_file_ignore_case(file_path: &str, str: &str) -> bool {
+    let Ok(content) = load_string_from_file(file_path) else {
+        return false;
+    };
+    let needle = str.to_lowercase();
+    content.to_lowercase().contains(&needle)
+}
+
+/// Returns whether `file_name` matches the shell-style glob `pattern`, where
+/// `*` matches any run of characters and `?` matches exactly one.
+fn file_matches_glob(file_name: &str, pattern: &str) -> bool {
+    glob_match(pattern.as_bytes(), file_name.as_bytes())
+}
+
+/// Iterative two-pointer glob matcher: on a mismatch it backtracks only to
+/// the most recently seen `*` (remembered in `star_idx`/`star_match`) rather
+/// than recursing on both branches, so it runs in O(pattern.len() *
+/// name.len()) with no recursion, unlike the naive backtracking matcher.
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    let (mut pi, mut ni) = (0usize, 0usize);
+    let mut star_idx = None;
+    let mut star_match = 0usize;
+
+    while ni < name.len() {
+        if pi < pattern.len() && (pattern[pi] == b'?' || pattern[pi] == name[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < pattern.len() && pattern[pi] == b'*' {
+            star_idx = Some(pi);
+            star_match = ni;
+            pi += 1;
+        } else if let Some(si) = star_idx {
+            pi = si + 1;
+            star_match += 1;
+            ni = star_match;
+        } else {
+            return false;
+        }
+    }
+    while pattern.get(pi) == Some(&b'*') {
+        pi += 1;
+    }
+    pi == pattern.len()
+}
+
+/// Returns whether the SHA-256 digest of the file at `file_path` equals
+/// `expected_hex`, a lowercase hex-encoded 32-byte digest.
+fn file_sha256_matches(file_path: &str, expected_hex: &str) -> bool {
+    let Ok(content) = load_buffer_from_file(file_path) else {
+        return false;
+    };
+    sha256_hex(&content).eq_ignore_ascii_case(expected_hex)
+}
+
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Computes the SHA-256 digest of `data` and returns it as lowercase hex.
+fn sha256_hex(data: &[u8]) -> String {
+    let mut h: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let bit_len = (data.len() as u64) * 8;
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend_from_slice(&bit_len.to_be_bytes());
+
+    for block in msg.chunks_exact(64) {
+        let mut w = [0u32; 64];
+        for (i, chunk) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        for i in 16..64 {
+            let s0 = w[i - 15].rotate_right(7) ^ w[i - 15].rotate_right(18) ^ (w[i - 15] >> 3);
+            let s1 = w[i - 2].rotate_right(17) ^ w[i - 2].rotate_right(19) ^ (w[i - 2] >> 10);
+            w[i] = w[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[i - 7])
+                .wrapping_add(s1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = h;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ ((!e) & g);
+            let temp1 = hh
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[i])
+                .wrapping_add(w[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let temp2 = s0.wrapping_add(maj);
+
+            hh = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(temp2);
+        }
+
+        h[0] = h[0].wrapping_add(a);
+        h[1] = h[1].wrapping_add(b);
+        h[2] = h[2].wrapping_add(c);
+        h[3] = h[3].wrapping_add(d);
+        h[4] = h[4].wrapping_add(e);
+        h[5] = h[5].wrapping_add(f);
+        h[6] = h[6].wrapping_add(g);
+        h[7] = h[7].wrapping_add(hh);
+    }
+
+    h.iter().map(|word| format!("{word:08x}")).collect()
+}
+
+/// C ABI re-export of [`string_exists_in_file_ignore_case`] for non-cxx C++
+/// translation units; the matching declaration is emitted by cbindgen into
+/// `file_ex_ffi.h` (see `base/cbindgen.toml`). `file_path` and `str` must be
+/// non-null, NUL-terminated, UTF-8 strings.
+#[no_mangle]
+pub unsafe extern "C" fn string_exists_in_file_ignore_case_c(
+    file_path: *const std::os::raw::c_char,
+    str: *const std::os::raw::c_char,
+) -> bool {
+    let (Ok(file_path), Ok(str)) = (
+        std::ffi::CStr::from_ptr(file_path).to_str(),
+        std::ffi::CStr::from_ptr(str).to_str(),
+    ) else {
+        return false;
+    };
+    string_exists_in_file_ignore_case(file_path, str)
+}
+
+/// C ABI re-export of [`file_matches_glob`]; see
+/// [`string_exists_in_file_ignore_case_c`] for the calling convention.
+#[no_mangle]
+pub unsafe extern "C" fn file_matches_glob_c(
+    file_name: *const std::os::raw::c_char,
+    pattern: *const std::os::raw::c_char,
+) -> bool {
+    let (Ok(file_name), Ok(pattern)) = (
+        std::ffi::CStr::from_ptr(file_name).to_str(),
+        std::ffi::CStr::from_ptr(pattern).to_str(),
+    ) else {
+        return false;
+    };
+    file_matches_glob(file_name, pattern)
+}
+
+/// C ABI re-export of [`file_sha256_matches`]; see
+/// [`string_exists_in_file_ignore_case_c`] for the calling convention.
+#[no_mangle]
+pub unsafe extern "C" fn file_sha256_matches_c(
+    file_path: *const std::os::raw::c_char,
+    expected_hex: *const std::os::raw::c_char,
+) -> bool {
+    let (Ok(file_path), Ok(expected_hex)) = (
+        std::ffi::CStr::from_ptr(file_path).to_str(),
+        std::ffi::CStr::from_ptr(expected_hex).to_str(),
+    ) else {
+        return false;
+    };
+    file_sha256_matches(file_path, expected_hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn temp_file_path(tag: &str) -> std::path::PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        std::env::temp_dir().join(format!("file_ex_test_{tag}_{}_{n}", std::process::id()))
+    }
+
+    fn write_temp_file(tag: &str, content: &[u8]) -> std::path::PathBuf {
+        let path = temp_file_path(tag);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn sha256_hex_known_answer_vectors() {
+        assert_eq!(
+            sha256_hex(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            sha256_hex(b"The quick brown fox jumps over the lazy dog"),
+            "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592"
+        );
+        assert_eq!(
+            sha256_hex(&vec![b'a'; 1_000_000]),
+            "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+        );
+    }
+
+    #[test]
+    fn glob_match_basic_cases() {
+        assert!(glob_match(b"*.txt", b"report.txt"));
+        assert!(!glob_match(b"*.txt", b"report.log"));
+        assert!(glob_match(b"file?.rs", b"file1.rs"));
+        assert!(!glob_match(b"file?.rs", b"file12.rs"));
+        assert!(glob_match(b"*", b""));
+        assert!(glob_match(b"", b""));
+        assert!(!glob_match(b"", b"x"));
+        assert!(glob_match(b"a*b*c", b"aXbXXc"));
+        assert!(!glob_match(b"a*b*c", b"aXbXXd"));
+    }
+
+    #[test]
+    fn glob_match_many_stars_does_not_hang() {
+        let pattern = "*".repeat(20) + "needle";
+        let name = "x".repeat(29) + "needle";
+        assert!(glob_match(pattern.as_bytes(), name.as_bytes()));
+
+        let non_matching_name = "x".repeat(35);
+        assert!(!glob_match(pattern.as_bytes(), non_matching_name.as_bytes()));
+    }
+
+    #[test]
+    fn read_vectored_at_reports_short_read() {
+        let path = write_temp_file("read_vectored", b"0123456789");
+        let mut first = [0u8; 4];
+        let mut second = [0u8; 10];
+        {
+            let mut segments = [
+                ReadSegment { offset: 0, buf: &mut first },
+                ReadSegment { offset: 4, buf: &mut second },
+            ];
+            let total = read_vectored_at(path.to_str().unwrap(), &mut segments).unwrap();
+            assert_eq!(total, 10);
+        }
+        assert_eq!(&first, b"0123");
+        assert_eq!(&second[..6], b"456789");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn write_vectored_at_writes_each_segment_at_its_offset() {
+        let path = write_temp_file("write_vectored", &[0u8; 4]);
+        let segments = [
+            WriteSegment { offset: 0, buf: b"ab" },
+            WriteSegment { offset: 2, buf: b"cd" },
+        ];
+        let total = write_vectored_at(path.to_str().unwrap(), &segments).unwrap();
+        assert_eq!(total, 4);
+        assert_eq!(std::fs::read(&path).unwrap(), b"abcd");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn load_file_chunked_splits_on_chunk_boundaries() {
+        let content = b"abcdefghij";
+        let path = write_temp_file("chunked", content);
+        let mut chunks = Vec::new();
+        load_file_chunked(path.to_str().unwrap(), 3, |chunk| {
+            chunks.push(chunk.to_vec());
+            true
+        })
+        .unwrap();
+        assert_eq!(
+            chunks,
+            vec![
+                b"abc".to_vec(),
+                b"def".to_vec(),
+                b"ghi".to_vec(),
+                b"j".to_vec(),
+            ]
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn for_each_line_and_count_lines_include_unterminated_last_line() {
+        let path = write_temp_file("lines", b"line one\nline two\nline three");
+        let mut lines = Vec::new();
+        for_each_line(path.to_str().unwrap(), |line| {
+            lines.push(line.to_string());
+            true
+        })
+        .unwrap();
+        assert_eq!(lines, vec!["line one", "line two", "line three"]);
+        assert_eq!(count_lines(path.to_str().unwrap()).unwrap(), 3);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn for_each_line_reassembles_a_line_spanning_multiple_internal_chunks() {
+        let long_line: String = "x".repeat(150_000);
+        let content = format!("{long_line}\nshort\n");
+        let path = write_temp_file("lines_long", content.as_bytes());
+        let mut lines = Vec::new();
+        for_each_line(path.to_str().unwrap(), |line| {
+            lines.push(line.to_string());
+            true
+        })
+        .unwrap();
+        assert_eq!(lines, vec![long_line, "short".to_string()]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn for_each_line_stops_early() {
+        let path = write_temp_file("lines_stop", b"a\nb\nc\n");
+        let mut lines = Vec::new();
+        for_each_line(path.to_str().unwrap(), |line| {
+            lines.push(line.to_string());
+            lines.len() < 2
+        })
+        .unwrap();
+        assert_eq!(lines, vec!["a", "b"]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn count_lines_handles_trailing_newline_and_empty_file() {
+        let with_trailing = write_temp_file("count_trailing", b"a\nb\n");
+        assert_eq!(count_lines(with_trailing.to_str().unwrap()).unwrap(), 2);
+        std::fs::remove_file(&with_trailing).unwrap();
+
+        let without_trailing = write_temp_file("count_no_trailing", b"a\nb");
+        assert_eq!(count_lines(without_trailing.to_str().unwrap()).unwrap(), 2);
+        std::fs::remove_file(&without_trailing).unwrap();
+
+        let empty = write_temp_file("count_empty", b"");
+        assert_eq!(count_lines(empty.to_str().unwrap()).unwrap(), 0);
+        std::fs::remove_file(&empty).unwrap();
     }
 }